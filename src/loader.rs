@@ -1,6 +1,8 @@
 pub struct Graph {
     pub hints: Vec<usize>,
     pub edges: Vec<Edge>,
+    pub rev_hints: Vec<usize>,
+    pub rev_edges: Vec<Edge>,
 }
 
 impl Graph {
@@ -27,12 +29,106 @@ impl Graph {
         }
         hints.push(line_count as usize);
 
-        Ok(Graph { hints, edges })
+        let max_to = edges.iter().map(|edge| edge.to).max().unwrap_or(0);
+        let (rev_hints, rev_edges) = build_csr(&edges, (hints.len() - 1).max(max_to), |edge| edge.to);
+
+        Ok(Graph { hints, edges, rev_hints, rev_edges })
+    }
+
+    pub fn new_with_cliques(path: &str) -> std::io::Result<(Self, Vec<bool>)> {
+        let content = std::fs::read_to_string(path)?;
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid data");
+
+        let lines: Vec<Vec<&str>> = content.lines().map(|line| line.split_whitespace().collect()).collect();
+
+        let mut node_count = 0;
+        for tokens in &lines {
+            let node_tokens: &[&str] = if tokens.len() == 3 {
+                &tokens[..2]
+            } else if tokens.len() > 3 {
+                &tokens[2..]
+            } else {
+                return Err(invalid());
+            };
+
+            for token in node_tokens {
+                let Ok(node) = token.parse::<usize>() else {
+                    return Err(invalid());
+                };
+                node_count = node_count.max(node);
+            }
+        }
+
+        let mut edges = Vec::new();
+        let mut hubs = Vec::new();
+
+        for tokens in &lines {
+            if tokens.len() == 3 {
+                let (Ok(from), Ok(to), Ok(weight)) = (tokens[0].parse(), tokens[1].parse(), tokens[2].parse()) else {
+                    return Err(invalid());
+                };
+                edges.push(Edge { from, to, weight });
+            } else if tokens.len() > 3 {
+                let Ok(weight) = tokens[1].parse::<usize>() else {
+                    return Err(invalid());
+                };
+
+                node_count += 1;
+                let hub = node_count;
+                hubs.push(hub);
+
+                let into_hub = weight - weight / 2;
+                let out_of_hub = weight / 2;
+                for token in &tokens[2..] {
+                    let Ok(member) = token.parse::<usize>() else {
+                        return Err(invalid());
+                    };
+                    edges.push(Edge { from: member, to: hub, weight: into_hub });
+                    edges.push(Edge { from: hub, to: member, weight: out_of_hub });
+                }
+            } else {
+                return Err(invalid());
+            }
+        }
+
+        let (hints, edges) = build_csr(&edges, node_count, |edge| edge.from);
+        let (rev_hints, rev_edges) = build_csr(&edges, node_count, |edge| edge.to);
+
+        let mut is_hub = vec![false; node_count + 1];
+        for hub in hubs {
+            is_hub[hub] = true;
+        }
+
+        Ok((Graph { hints, edges, rev_hints, rev_edges }, is_hub))
     }
 
     pub fn children(&self, node: usize) -> &[Edge] {
         &self.edges[self.hints[node - 1]..self.hints[node]]
     }
+
+    pub fn parents(&self, node: usize) -> &[Edge] {
+        &self.rev_edges[self.rev_hints[node - 1]..self.rev_hints[node]]
+    }
+}
+
+fn build_csr(edges: &[Edge], node_count: usize, key: impl Fn(&Edge) -> usize) -> (Vec<usize>, Vec<Edge>) {
+    let mut hints = vec![0; node_count + 1];
+    for edge in edges {
+        hints[key(edge)] += 1;
+    }
+    for node in 1..=node_count {
+        hints[node] += hints[node - 1];
+    }
+
+    let mut sorted = vec![Edge { from: 0, to: 0, weight: 0 }; edges.len()];
+    let mut cursor = hints.clone();
+    for &edge in edges.iter().rev() {
+        let k = key(&edge);
+        cursor[k] -= 1;
+        sorted[cursor[k]] = edge;
+    }
+
+    (hints, sorted)
 }
 
 #[derive(Debug, Clone, Copy)]
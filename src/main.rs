@@ -7,17 +7,32 @@ use std::{fmt::Write};
 use loader::{Edge, Graph};
 
 mod loader;
+mod tsort;
 
 fn main() {
     let mut args = std::env::args().skip(1);
 
-    let (
-        Some(algorithm),
-        Some(graph_file),
-    ) = (
-        args.next(),
-        args.next(),
-    ) else {
+    let Some(algorithm) = args.next() else {
+        eprintln!("usage: <algorithm> <graph_file> <...>");
+        return;
+    };
+
+    if algorithm == "tsort" {
+        run_tsort(args);
+        return;
+    }
+
+    if algorithm == "isomorphic" {
+        run_isomorphic(args);
+        return;
+    }
+
+    if algorithm == "label-set-clique" {
+        run_label_set_clique(args);
+        return;
+    }
+
+    let Some(graph_file) = args.next() else {
         eprintln!("usage: <algorithm> <graph_file> <...>");
         return;
     };
@@ -32,8 +47,11 @@ fn main() {
 
     match algorithm.as_str() {
         "label-set" => run_label_set(args, &graph),
+        "label-set-lex" => run_label_set_lex(args, &graph),
+        "label-set-bi" => run_label_set_bi(args, &graph),
         "kruskal" => run_kruskal(&graph),
         "monotone-ordering" => run_monotone_ordering(&graph),
+        "layout" => run_layout(&graph),
         _ => eprintln!("unknown algorithm: {}", algorithm),
     }
 }
@@ -73,29 +91,36 @@ fn run_label_set(mut args: impl Iterator<Item = String>, graph: &Graph) {
 fn label_set(start: usize, end: usize, graph: &loader::Graph) -> Option<(Vec<usize>, usize)> {
     let mut t = vec![1 << 32isize; graph.hints.len()];
     let mut x = vec![None; graph.hints.len()];
-    
+    let mut settled = vec![false; graph.hints.len()];
+
     t[start] = 0;
 
     let mut e = vec![start];
 
     while let Some(node) = e.pop() {
+        if settled[node] {
+            continue;
+        }
+        settled[node] = true;
+
+        if node == end {
+            let mut path = vec![];
+            let mut current = Some(end);
+            while let Some(cur) = current {
+                path.push(cur);
+                current = x[cur];
+            }
+
+            let explored = x.iter().filter(|elem| elem.is_some()).count();
+            println!("Visited {} nodes, that is {}%", explored, explored as f32 / x.len() as f32 * 100.0);
+
+            return Some((path, t[end]));
+        }
+
         for &Edge { to, weight, .. } in graph.children(node) {
             if t[to] > t[node] + weight {
                 t[to] = t[node] + weight;
                 x[to] = Some(node);
-                if to == end {
-                    let mut path = vec![];
-                    let mut current = Some(end);
-                    while let Some(cur) = current {
-                        path.push(cur);
-                        current = x[cur];
-                    }
-
-                    let explored = x.iter().filter(|elem| elem.is_some()).count();
-                    println!("Visited {} nodes, that is {}%", explored, explored as f32 / x.len() as f32 * 100.0);
-
-                    return Some((path, t[end]));
-                }
 
                 let Err(pos) = e.binary_search_by(|&i| t[to].cmp(&t[i])) else {
                     continue;
@@ -113,6 +138,303 @@ fn label_set(start: usize, end: usize, graph: &loader::Graph) -> Option<(Vec<usi
     return None;
 }
 
+fn run_label_set_lex(mut args: impl Iterator<Item = String>, graph: &Graph) {
+    let (
+        Some(Ok(start)),
+        Some(Ok(end)),
+    ) = (
+        args.next().map(|start| start.parse::<usize>()),
+        args.next().map(|end| end.parse::<usize>())
+    ) else {
+        eprintln!("Expected command format: label-set-lex <file> <start: uint> <end: uint>");
+        return;
+    };
+
+    let now = std::time::Instant::now();
+    let Some((path, cost)) = label_set_lex(start, end, &graph) else {
+        eprintln!("No path Found between nodes {} and {}.", start, end);
+        return;
+    };
+    println!("Label-Set-Lex ran for: {:?}", now.elapsed());
+
+    let mut output = String::with_capacity(
+        path.iter()
+            .fold(0, |acc, &i| acc + i.log10() as usize + " -> ".len()),
+    );
+
+    for &node in &path[..path.len() - 1] {
+        write!(output, "{} -> ", node).unwrap();
+    }
+    writeln!(output, "{}", path[path.len() - 1]).unwrap();
+
+    println!("Path with cost {} is:\n{}", cost, output);
+}
+
+fn label_set_lex(start: usize, end: usize, graph: &Graph) -> Option<(Vec<usize>, usize)> {
+    let mut dist_to_end = vec![1 << 32isize; graph.hints.len()];
+    dist_to_end[end] = 0;
+
+    let mut e = vec![end];
+
+    while let Some(node) = e.pop() {
+        for &Edge { from, weight, .. } in graph.parents(node) {
+            if dist_to_end[from] > dist_to_end[node] + weight {
+                dist_to_end[from] = dist_to_end[node] + weight;
+
+                let pos = e.binary_search_by(|&i| dist_to_end[from].cmp(&dist_to_end[i])).unwrap_or_else(|pos| pos);
+                e.insert(pos, from);
+            }
+        }
+    }
+
+    if dist_to_end[start] >= 1 << 32isize {
+        return None;
+    }
+
+    let mut path = vec![start];
+    let mut visited = vec![false; graph.hints.len()];
+    visited[start] = true;
+    let mut current = start;
+
+    while current != end {
+        let mut best = None;
+        for &Edge { to, weight, .. } in graph.children(current) {
+            if dist_to_end[to] + weight == dist_to_end[current]
+                && !visited[to]
+                && best.map_or(true, |b| to < b)
+            {
+                best = Some(to);
+            }
+        }
+
+        let Some(next) = best else {
+            return None;
+        };
+
+        path.push(next);
+        visited[next] = true;
+        current = next;
+    }
+
+    Some((path, dist_to_end[start]))
+}
+
+fn run_label_set_bi(mut args: impl Iterator<Item = String>, graph: &Graph) {
+    let (
+        Some(Ok(start)),
+        Some(Ok(end)),
+    ) = (
+        args.next().map(|start| start.parse::<usize>()),
+        args.next().map(|end| end.parse::<usize>())
+    ) else {
+        eprintln!("Expected command format: label-set-bi <file> <start: uint> <end: uint>");
+        return;
+    };
+
+    let now = std::time::Instant::now();
+    let Some((path, cost)) = label_set_bi(start, end, &graph) else {
+        eprintln!("No path Found between nodes {} and {}.", start, end);
+        return;
+    };
+    println!("Label-Set-Bi ran for: {:?}", now.elapsed());
+
+    let mut output = String::with_capacity(
+        path.iter()
+            .fold(0, |acc, &i| acc + i.log10() as usize + " -> ".len()),
+    );
+
+    for &node in &path[..path.len() - 1] {
+        write!(output, "{} -> ", node).unwrap();
+    }
+    writeln!(output, "{}", path[path.len() - 1]).unwrap();
+
+    println!("Path with cost {} is:\n{}", cost, output);
+}
+
+fn label_set_bi(start: usize, end: usize, graph: &Graph) -> Option<(Vec<usize>, usize)> {
+    let n = graph.hints.len();
+
+    let mut dist_fwd = vec![1 << 32isize; n];
+    let mut dist_bwd = vec![1 << 32isize; n];
+    let mut x_fwd = vec![None; n];
+    let mut x_bwd = vec![None; n];
+    let mut settled_fwd = vec![false; n];
+    let mut settled_bwd = vec![false; n];
+
+    dist_fwd[start] = 0;
+    dist_bwd[end] = 0;
+
+    let mut e_fwd = vec![start];
+    let mut e_bwd = vec![end];
+
+    let mut mu = 1 << 32isize;
+    let mut meeting = None;
+
+    if start == end {
+        mu = 0;
+        meeting = Some(start);
+    }
+
+    loop {
+        let key_fwd = e_fwd.last().map_or(1 << 32isize, |&node| dist_fwd[node]);
+        let key_bwd = e_bwd.last().map_or(1 << 32isize, |&node| dist_bwd[node]);
+
+        if key_fwd + key_bwd >= mu {
+            break;
+        }
+
+        if key_fwd <= key_bwd {
+            let node = e_fwd.pop().unwrap();
+            if settled_fwd[node] {
+                continue;
+            }
+            settled_fwd[node] = true;
+
+            if settled_bwd[node] && dist_fwd[node] + dist_bwd[node] < mu {
+                mu = dist_fwd[node] + dist_bwd[node];
+                meeting = Some(node);
+            }
+
+            for &Edge { to, weight, .. } in graph.children(node) {
+                if dist_fwd[to] > dist_fwd[node] + weight {
+                    dist_fwd[to] = dist_fwd[node] + weight;
+                    x_fwd[to] = Some(node);
+
+                    if settled_bwd[to] && dist_fwd[to] + dist_bwd[to] < mu {
+                        mu = dist_fwd[to] + dist_bwd[to];
+                        meeting = Some(to);
+                    }
+
+                    let pos = e_fwd.binary_search_by(|&i| dist_fwd[to].cmp(&dist_fwd[i])).unwrap_or_else(|pos| pos);
+                    e_fwd.insert(pos, to);
+                }
+            }
+        } else {
+            let node = e_bwd.pop().unwrap();
+            if settled_bwd[node] {
+                continue;
+            }
+            settled_bwd[node] = true;
+
+            if settled_fwd[node] && dist_fwd[node] + dist_bwd[node] < mu {
+                mu = dist_fwd[node] + dist_bwd[node];
+                meeting = Some(node);
+            }
+
+            for &Edge { from, weight, .. } in graph.parents(node) {
+                if dist_bwd[from] > dist_bwd[node] + weight {
+                    dist_bwd[from] = dist_bwd[node] + weight;
+                    x_bwd[from] = Some(node);
+
+                    if settled_fwd[from] && dist_fwd[from] + dist_bwd[from] < mu {
+                        mu = dist_fwd[from] + dist_bwd[from];
+                        meeting = Some(from);
+                    }
+
+                    let pos = e_bwd.binary_search_by(|&i| dist_bwd[from].cmp(&dist_bwd[i])).unwrap_or_else(|pos| pos);
+                    e_bwd.insert(pos, from);
+                }
+            }
+        }
+    }
+
+    let meeting = meeting?;
+
+    let mut path = vec![];
+    let mut current = Some(meeting);
+    while let Some(cur) = current {
+        path.push(cur);
+        current = x_fwd[cur];
+    }
+    path.reverse();
+
+    let mut current = x_bwd[meeting];
+    while let Some(cur) = current {
+        path.push(cur);
+        current = x_bwd[cur];
+    }
+
+    let explored = settled_fwd.iter().filter(|&&s| s).count() + settled_bwd.iter().filter(|&&s| s).count();
+    println!("Visited {} nodes, that is {}%", explored, explored as f32 / (n * 2) as f32 * 100.0);
+
+    Some((path, mu))
+}
+
+fn run_tsort(mut args: impl Iterator<Item = String>) {
+    let source = args.next().unwrap_or_else(|| "-".to_string());
+
+    let content = if source == "-" {
+        let mut buf = String::new();
+        if let Err(err) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf) {
+            println!("Unable to read stdin: {}", err);
+            return;
+        }
+        buf
+    } else {
+        match std::fs::read_to_string(&source) {
+            Ok(content) => content,
+            Err(err) => {
+                println!("Unable to load graph: {}", err);
+                return;
+            }
+        }
+    };
+
+    let dag = tsort::Dag::parse(&content);
+
+    match dag.tsort() {
+        Ok(order) => println!("Topological order is:\n{}", order.join(" ")),
+        Err(cyclic) => eprintln!(
+            "No topological order, graph contains a cycle. Nodes on or reachable from a cycle:\n{}",
+            cyclic.join(" ")
+        ),
+    }
+}
+
+fn run_label_set_clique(mut args: impl Iterator<Item = String>) {
+    let (
+        Some(graph_file),
+        Some(Ok(start)),
+        Some(Ok(end)),
+    ) = (
+        args.next(),
+        args.next().map(|start| start.parse::<usize>()),
+        args.next().map(|end| end.parse::<usize>()),
+    ) else {
+        eprintln!("Expected command format: label-set-clique <file> <start: uint> <end: uint>");
+        return;
+    };
+
+    let (graph, is_hub) = match Graph::new_with_cliques(&graph_file) {
+        Ok(result) => result,
+        Err(err) => {
+            println!("Unable to load graph: {}", err);
+            return;
+        }
+    };
+
+    let now = std::time::Instant::now();
+    let Some((path, cost)) = label_set(start, end, &graph) else {
+        eprintln!("No path Found between nodes {} and {}.", start, end);
+        return;
+    };
+    println!("Label-Set-Clique ran for: {:?}", now.elapsed());
+
+    let path: Vec<usize> = path.into_iter().filter(|&node| !is_hub[node]).collect();
+
+    let mut output = String::with_capacity(
+        path.iter()
+            .fold(0, |acc, &i| acc + i.log10() as usize + " -> ".len()),
+    );
+
+    for &node in path[1..].iter().rev() {
+        write!(output, "{} -> ", node).unwrap();
+    }
+    writeln!(output, "{}", path[0]).unwrap();
+
+    println!("Path with cost {} is:\n{}", cost, output);
+}
+
 fn run_monotone_ordering(graph: &Graph) {
     let Some(ordering) = monotone_ordering(graph) else {
         eprintln!("No monotone ordering, graph contains cycles.");
@@ -121,7 +443,7 @@ fn run_monotone_ordering(graph: &Graph) {
 
     let mut output = String::with_capacity(
         ordering.iter()
-            .fold(0, |acc, &i| acc + i.log10() as usize + " ".len()),
+            .fold(0, |acc, &i| acc + i.max(1).log10() as usize + " ".len()),
     );
 
     for &node in ordering.iter() {
@@ -150,9 +472,9 @@ fn monotone_ordering(graph: &Graph) -> Option<Vec<usize>> {
                 }
                 ordering[node] = counter;
                 counter += 1;
-                true
-            } else {
                 false
+            } else {
+                true
             }
         });
         
@@ -217,3 +539,395 @@ fn kruskal(graph: &loader::Graph) -> Option<Vec<Edge>> {
 
     return Some(result);
 }
+
+fn run_isomorphic(mut args: impl Iterator<Item = String>) {
+    let (
+        Some(file_a),
+        Some(file_b),
+    ) = (
+        args.next(),
+        args.next(),
+    ) else {
+        eprintln!("Expected command format: isomorphic <file_a> <file_b>");
+        return;
+    };
+
+    let graph_a = match Graph::new(&file_a) {
+        Ok(graph) => graph,
+        Err(err) => {
+            println!("Unable to load graph: {}", err);
+            return;
+        }
+    };
+    let graph_b = match Graph::new(&file_b) {
+        Ok(graph) => graph,
+        Err(err) => {
+            println!("Unable to load graph: {}", err);
+            return;
+        }
+    };
+
+    let now = std::time::Instant::now();
+    let Some(mapping) = isomorphic(&graph_a, &graph_b) else {
+        println!("Graphs are not isomorphic.");
+        return;
+    };
+    println!("Isomorphic ran for: {:?}", now.elapsed());
+
+    let mut output = String::new();
+    for (node, &mapped) in mapping.iter().enumerate().skip(1) {
+        writeln!(output, "{} -> {}", node, mapped).unwrap();
+    }
+
+    println!("Graphs are isomorphic, mapping:\n{}", output);
+}
+
+fn node_count(graph: &Graph) -> usize {
+    graph.edges.iter().map(|edge| edge.from.max(edge.to)).max().unwrap_or(0) + 1
+}
+
+fn out_edges(graph: &Graph, node: usize) -> &[Edge] {
+    if node < graph.hints.len() { graph.children(node) } else { &[] }
+}
+
+fn degree_sequence(graph: &Graph, n: usize) -> Vec<usize> {
+    let mut degrees = (1..n)
+        .map(|node| out_edges(graph, node).len())
+        .collect::<Vec<_>>();
+    degrees.sort_unstable();
+    degrees
+}
+
+fn isomorphic(a: &Graph, b: &Graph) -> Option<Vec<usize>> {
+    let n = node_count(a);
+    if n != node_count(b) || a.edges.len() != b.edges.len() {
+        return None;
+    }
+    if degree_sequence(a, n) != degree_sequence(b, n) {
+        return None;
+    }
+
+    let mut core_a = vec![None; n];
+    let mut core_b = vec![None; n];
+    let mut term_a_out = vec![0; n];
+    let mut term_a_in = vec![0; n];
+    let mut term_b_out = vec![0; n];
+    let mut term_b_in = vec![0; n];
+
+    let matched = vf2_match(
+        a, b,
+        &mut core_a, &mut core_b,
+        &mut term_a_out, &mut term_a_in,
+        &mut term_b_out, &mut term_b_in,
+        n, 0,
+    );
+
+    if matched {
+        Some(core_a.into_iter().map(|m| m.unwrap_or(0)).collect())
+    } else {
+        None
+    }
+}
+
+fn vf2_match(
+    a: &Graph, b: &Graph,
+    core_a: &mut Vec<Option<usize>>, core_b: &mut Vec<Option<usize>>,
+    term_a_out: &mut Vec<usize>, term_a_in: &mut Vec<usize>,
+    term_b_out: &mut Vec<usize>, term_b_in: &mut Vec<usize>,
+    n: usize, depth: usize,
+) -> bool {
+    if core_a[1..n].iter().all(Option::is_some) {
+        return true;
+    }
+
+    let Some((node_a, candidates_b)) = next_candidates(
+        core_a, core_b, term_a_out, term_a_in, term_b_out, term_b_in, n,
+    ) else {
+        return false;
+    };
+
+    for node_b in candidates_b {
+        if core_b[node_b].is_some() {
+            continue;
+        }
+        if !feasible(a, b, core_a, core_b, node_a, node_b) {
+            continue;
+        }
+        if !lookahead_ok(
+            a, b, core_a, core_b, term_a_out, term_a_in, term_b_out, term_b_in, node_a, node_b,
+        ) {
+            continue;
+        }
+
+        core_a[node_a] = Some(node_b);
+        core_b[node_b] = Some(node_a);
+        push_terminals(a, core_a, term_a_out, term_a_in, node_a, depth + 1);
+        push_terminals(b, core_b, term_b_out, term_b_in, node_b, depth + 1);
+
+        if vf2_match(a, b, core_a, core_b, term_a_out, term_a_in, term_b_out, term_b_in, n, depth + 1) {
+            return true;
+        }
+
+        core_a[node_a] = None;
+        core_b[node_b] = None;
+        reset_at_depth(term_a_out, depth + 1);
+        reset_at_depth(term_a_in, depth + 1);
+        reset_at_depth(term_b_out, depth + 1);
+        reset_at_depth(term_b_in, depth + 1);
+    }
+
+    false
+}
+
+fn next_candidates(
+    core_a: &[Option<usize>], core_b: &[Option<usize>],
+    term_a_out: &[usize], term_a_in: &[usize],
+    term_b_out: &[usize], term_b_in: &[usize],
+    n: usize,
+) -> Option<(usize, Vec<usize>)> {
+    let a_out: Vec<usize> = (1..n).filter(|&v| core_a[v].is_none() && term_a_out[v] != 0).collect();
+    let b_out: Vec<usize> = (1..n).filter(|&v| core_b[v].is_none() && term_b_out[v] != 0).collect();
+    if !a_out.is_empty() && !b_out.is_empty() {
+        return Some((a_out[0], b_out));
+    }
+
+    let a_in: Vec<usize> = (1..n).filter(|&v| core_a[v].is_none() && term_a_in[v] != 0).collect();
+    let b_in: Vec<usize> = (1..n).filter(|&v| core_b[v].is_none() && term_b_in[v] != 0).collect();
+    if !a_in.is_empty() && !b_in.is_empty() {
+        return Some((a_in[0], b_in));
+    }
+
+    let a_rest: Vec<usize> = (1..n).filter(|&v| core_a[v].is_none()).collect();
+    let b_rest: Vec<usize> = (1..n).filter(|&v| core_b[v].is_none()).collect();
+
+    a_rest.first().map(|&node| (node, b_rest))
+}
+
+fn feasible(
+    a: &Graph, b: &Graph,
+    core_a: &[Option<usize>], core_b: &[Option<usize>],
+    node_a: usize, node_b: usize,
+) -> bool {
+    for &Edge { to, .. } in out_edges(a, node_a) {
+        if let Some(mapped) = core_a[to] {
+            if !out_edges(b, node_b).iter().any(|e| e.to == mapped) {
+                return false;
+            }
+        }
+    }
+    for &Edge { from, .. } in a.parents(node_a) {
+        if let Some(mapped) = core_a[from] {
+            if !b.parents(node_b).iter().any(|e| e.from == mapped) {
+                return false;
+            }
+        }
+    }
+    for &Edge { to, .. } in out_edges(b, node_b) {
+        if let Some(mapped) = core_b[to] {
+            if !out_edges(a, node_a).iter().any(|e| e.to == mapped) {
+                return false;
+            }
+        }
+    }
+    for &Edge { from, .. } in b.parents(node_b) {
+        if let Some(mapped) = core_b[from] {
+            if !a.parents(node_a).iter().any(|e| e.from == mapped) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn lookahead_ok(
+    a: &Graph, b: &Graph,
+    core_a: &[Option<usize>], core_b: &[Option<usize>],
+    term_a_out: &[usize], term_a_in: &[usize],
+    term_b_out: &[usize], term_b_in: &[usize],
+    node_a: usize, node_b: usize,
+) -> bool {
+    let a_out = count_unmapped(out_edges(a, node_a).iter().map(|e| e.to), core_a, term_a_out);
+    let b_out = count_unmapped(out_edges(b, node_b).iter().map(|e| e.to), core_b, term_b_out);
+    if a_out != b_out {
+        return false;
+    }
+
+    let a_in = count_unmapped(a.parents(node_a).iter().map(|e| e.from), core_a, term_a_in);
+    let b_in = count_unmapped(b.parents(node_b).iter().map(|e| e.from), core_b, term_b_in);
+
+    a_in == b_in
+}
+
+fn count_unmapped(
+    neighbors: impl Iterator<Item = usize>,
+    core: &[Option<usize>],
+    term: &[usize],
+) -> (usize, usize) {
+    let mut in_term = 0;
+    let mut new = 0;
+
+    for node in neighbors {
+        if core[node].is_some() {
+            continue;
+        }
+        if term[node] != 0 {
+            in_term += 1;
+        } else {
+            new += 1;
+        }
+    }
+
+    (in_term, new)
+}
+
+fn push_terminals(
+    graph: &Graph,
+    core: &[Option<usize>],
+    term_out: &mut [usize], term_in: &mut [usize],
+    node: usize, depth: usize,
+) {
+    for &Edge { to, .. } in out_edges(graph, node) {
+        if core[to].is_none() && term_out[to] == 0 {
+            term_out[to] = depth;
+        }
+    }
+    for &Edge { from, .. } in graph.parents(node) {
+        if core[from].is_none() && term_in[from] == 0 {
+            term_in[from] = depth;
+        }
+    }
+}
+
+fn reset_at_depth(term: &mut [usize], depth: usize) {
+    for value in term.iter_mut() {
+        if *value == depth {
+            *value = 0;
+        }
+    }
+}
+
+fn run_layout(graph: &Graph) {
+    let Some((layer, position)) = layout(graph) else {
+        eprintln!("No layout, graph contains cycles.");
+        return;
+    };
+
+    let mut output = String::new();
+    for node in 1..graph.hints.len() {
+        writeln!(output, "{}: layer {}, position {}", node, layer[node], position[node]).unwrap();
+    }
+
+    println!("Layout is:\n{}", output);
+}
+
+fn layout(graph: &Graph) -> Option<(Vec<usize>, Vec<usize>)> {
+    let n = graph.hints.len();
+    let ordering = monotone_ordering(graph)?;
+
+    let mut nodes_by_rank: Vec<usize> = (1..n).collect();
+    nodes_by_rank.sort_by_key(|&node| ordering[node]);
+
+    let mut layer = vec![0; n];
+    for &node in &nodes_by_rank {
+        layer[node] = graph.parents(node)
+            .iter()
+            .map(|edge| layer[edge.from] + 1)
+            .max()
+            .unwrap_or(0);
+    }
+
+    let (layer_of, up, down) = build_layered(graph, &layer, n);
+    let mut layers = group_by_layer(&layer_of);
+    barycenter_sweeps(&mut layers, &up, &down, 4);
+
+    let mut position = vec![0; n];
+    for nodes in &layers {
+        for (pos, &id) in nodes.iter().enumerate() {
+            if id < n {
+                position[id] = pos;
+            }
+        }
+    }
+
+    Some((layer, position))
+}
+
+fn build_layered(graph: &Graph, layer: &[usize], n: usize) -> (Vec<usize>, Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let mut layer_of = layer.to_vec();
+    let mut up: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut down: Vec<Vec<usize>> = vec![Vec::new(); n];
+
+    for from_node in 1..n {
+        for &Edge { to: to_node, .. } in graph.children(from_node) {
+            let mut prev = from_node;
+            for virtual_layer in (layer_of[from_node] + 1)..layer_of[to_node] {
+                let id = layer_of.len();
+                layer_of.push(virtual_layer);
+                up.push(Vec::new());
+                down.push(Vec::new());
+
+                down[prev].push(id);
+                up[id].push(prev);
+                prev = id;
+            }
+            down[prev].push(to_node);
+            up[to_node].push(prev);
+        }
+    }
+
+    (layer_of, up, down)
+}
+
+fn group_by_layer(layer_of: &[usize]) -> Vec<Vec<usize>> {
+    let layer_count = layer_of[1..].iter().max().map_or(0, |&max| max + 1);
+    let mut layers = vec![Vec::new(); layer_count];
+    for (id, &l) in layer_of.iter().enumerate().skip(1) {
+        layers[l].push(id);
+    }
+    layers
+}
+
+fn barycenter_sweeps(layers: &mut [Vec<usize>], up: &[Vec<usize>], down: &[Vec<usize>], sweeps: usize) {
+    let mut position = vec![0; up.len()];
+    for layer in layers.iter() {
+        for (pos, &id) in layer.iter().enumerate() {
+            position[id] = pos;
+        }
+    }
+
+    for sweep in 0..sweeps {
+        if sweep % 2 == 0 {
+            for l in 1..layers.len() {
+                sort_by_barycenter(&mut layers[l], up, &position);
+                for (pos, &id) in layers[l].iter().enumerate() {
+                    position[id] = pos;
+                }
+            }
+        } else {
+            for l in (0..layers.len().saturating_sub(1)).rev() {
+                sort_by_barycenter(&mut layers[l], down, &position);
+                for (pos, &id) in layers[l].iter().enumerate() {
+                    position[id] = pos;
+                }
+            }
+        }
+    }
+}
+
+fn sort_by_barycenter(layer: &mut [usize], neighbors: &[Vec<usize>], position: &[usize]) {
+    layer.sort_by(|&a, &b| {
+        barycenter(a, neighbors, position)
+            .partial_cmp(&barycenter(b, neighbors, position))
+            .unwrap()
+    });
+}
+
+fn barycenter(id: usize, neighbors: &[Vec<usize>], position: &[usize]) -> f64 {
+    let of_id = &neighbors[id];
+    if of_id.is_empty() {
+        return position[id] as f64;
+    }
+
+    of_id.iter().map(|&n| position[n] as f64).sum::<f64>() / of_id.len() as f64
+}
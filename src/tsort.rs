@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+pub struct Dag {
+    pub labels: Vec<String>,
+    pub successors: Vec<Vec<usize>>,
+    pub in_degree: Vec<usize>,
+}
+
+impl Dag {
+    pub fn parse(content: &str) -> Self {
+        let mut dag = Dag { labels: Vec::new(), successors: Vec::new(), in_degree: Vec::new() };
+        let mut ids = HashMap::new();
+
+        for line in content.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(predecessor), Some(successor)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+
+            let from = dag.intern(&mut ids, predecessor);
+            let to = dag.intern(&mut ids, successor);
+
+            dag.successors[from].push(to);
+            dag.in_degree[to] += 1;
+        }
+
+        dag
+    }
+
+    fn intern(&mut self, ids: &mut HashMap<String, usize>, label: &str) -> usize {
+        if let Some(&id) = ids.get(label) {
+            return id;
+        }
+
+        let id = self.labels.len();
+        ids.insert(label.to_string(), id);
+        self.labels.push(label.to_string());
+        self.successors.push(Vec::new());
+        self.in_degree.push(0);
+
+        id
+    }
+
+    pub fn tsort(&self) -> Result<Vec<String>, Vec<String>> {
+        let mut in_degree = self.in_degree.clone();
+        let mut queue: Vec<usize> = (0..self.labels.len()).filter(|&id| in_degree[id] == 0).collect();
+        let mut order = Vec::with_capacity(self.labels.len());
+
+        let mut head = 0;
+        while head < queue.len() {
+            let node = queue[head];
+            head += 1;
+            order.push(self.labels[node].clone());
+
+            for &successor in &self.successors[node] {
+                in_degree[successor] -= 1;
+                if in_degree[successor] == 0 {
+                    queue.push(successor);
+                }
+            }
+        }
+
+        if order.len() == self.labels.len() {
+            return Ok(order);
+        }
+
+        let cyclic = (0..self.labels.len())
+            .filter(|&id| in_degree[id] != 0)
+            .map(|id| self.labels[id].clone())
+            .collect();
+
+        Err(cyclic)
+    }
+}